@@ -0,0 +1,94 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::traverser::Traverser;
+use bit_set::BitSet;
+use dyn_type::Object;
+use pegasus::api::function::*;
+
+/// TinkerPop's `T` pseudo-property marker, used to key the synthetic `id`/`label`
+/// entries so a real property named `id` or `label` can't collide with them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum T {
+    Id,
+    Label,
+}
+
+/// `elementMap()`: flattens the head vertex/edge of the traverser into a single
+/// `id`/`label` + property `Object` map.
+pub struct ElementMapStep {
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+    /// Property keys to include; an empty list means all properties.
+    pub keys: Vec<String>,
+}
+
+impl MapFunction<Traverser, Traverser> for ElementMapStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let element = input
+            .get_element()
+            .ok_or("ElementMapStep expects the traverser head to be a graph element")?;
+        let mut entries = vec![
+            (Object::DynOwned(Box::new(T::Id)), Object::from(element.id())),
+            (Object::DynOwned(Box::new(T::Label)), Object::from(element.label().to_string())),
+        ];
+        if self.keys.is_empty() {
+            for (key, value) in element.get_all_properties() {
+                entries.push((Object::from(key), value));
+            }
+        } else {
+            for key in &self.keys {
+                if let Some(value) = element.get_property(key) {
+                    entries.push((Object::from(key.clone()), value));
+                }
+            }
+        }
+        input.split_with_value(Object::KV(entries), &self.tags);
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::traversal::traverser::GraphElement;
+
+    #[test]
+    fn synthetic_keys_do_not_collide_with_a_real_id_or_label_property() {
+        let mut element = GraphElement::new(7, "person");
+        element.set_property("id", Object::from("decoy-id"));
+        element.set_property("label", Object::from("decoy-label"));
+        let input = Traverser::with_element(element);
+
+        let step = ElementMapStep { tags: BitSet::new(), remove_tags: BitSet::new(), keys: vec![] };
+        let output = step.exec(input).unwrap();
+
+        let entries = match output.get_object().unwrap() {
+            Object::KV(entries) => entries.clone(),
+            other => panic!("expected Object::KV, got {:?}", other),
+        };
+
+        let t_id_count = entries.iter().filter(|(k, _)| k == &Object::DynOwned(Box::new(T::Id))).count();
+        let string_id_count = entries.iter().filter(|(k, _)| k == &Object::from("id")).count();
+        assert_eq!(t_id_count, 1, "the synthetic T::Id entry must be present exactly once");
+        assert_eq!(string_id_count, 1, "the real \"id\" property must survive alongside it");
+
+        let t_label_count = entries.iter().filter(|(k, _)| k == &Object::DynOwned(Box::new(T::Label))).count();
+        let string_label_count = entries.iter().filter(|(k, _)| k == &Object::from("label")).count();
+        assert_eq!(t_label_count, 1, "the synthetic T::Label entry must be present exactly once");
+        assert_eq!(string_label_count, 1, "the real \"label\" property must survive alongside it");
+    }
+}
@@ -13,16 +13,93 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::generated::gremlin as pb;
 use crate::process::traversal::traverser::Traverser;
 use bit_set::BitSet;
 use dyn_type::Object;
 use pegasus::api::function::*;
 
-impl MapFunction<Traverser, Traverser> for pb::PathStep {
+/// A `by()` projection over a path element's raw value; `None` means no value rather
+/// than an error, so a real modulator error still propagates through `FnResult`.
+pub type PathModulator = Box<dyn MapFunction<Object, Option<Object>>>;
+
+/// `path()` with optional `by()` modulators. A plain runtime struct built from
+/// `pb::PathStep` during plan construction, since `modulators` holds trait objects that
+/// can't ride along on `pb::PathStep` itself.
+pub struct PathStep {
+    pub modulators: Vec<PathModulator>,
+}
+
+impl MapFunction<Traverser, Traverser> for PathStep {
     fn exec(&self, input: Traverser) -> FnResult<Traverser> {
+        let mut input = input;
+        let bulk = input.get_bulk();
         let path = input.take_path();
-        Ok(Traverser::Object(Object::DynOwned(Box::new(path))))
+        let projected = project_path(path, &self.modulators)?;
+        let mut output = Traverser::new(Object::DynOwned(Box::new(projected)));
+        output.set_bulk(bulk);
+        Ok(output)
+    }
+}
+
+/// Applies `modulators[i % modulators.len()]` to path element `i`, dropping elements
+/// projected to `None`. Empty `modulators` preserves the whole path.
+fn project_path(path: Vec<Object>, modulators: &[PathModulator]) -> FnResult<Vec<Object>> {
+    if modulators.is_empty() {
+        return Ok(path);
+    }
+    let mut projected = Vec::with_capacity(path.len());
+    for (i, elem) in path.into_iter().enumerate() {
+        let modulator = &modulators[i % modulators.len()];
+        if let Some(value) = modulator.exec(elem)? {
+            projected.push(value);
+        }
+    }
+    Ok(projected)
+}
+
+#[cfg(test)]
+mod path_step_tests {
+    use super::*;
+
+    struct AlwaysSome(i64);
+    impl MapFunction<Object, Option<Object>> for AlwaysSome {
+        fn exec(&self, _input: Object) -> FnResult<Option<Object>> {
+            Ok(Some(Object::from(self.0)))
+        }
+    }
+
+    struct AlwaysNone;
+    impl MapFunction<Object, Option<Object>> for AlwaysNone {
+        fn exec(&self, _input: Object) -> FnResult<Option<Object>> {
+            Ok(None)
+        }
+    }
+
+    struct AlwaysErr;
+    impl MapFunction<Object, Option<Object>> for AlwaysErr {
+        fn exec(&self, _input: Object) -> FnResult<Option<Object>> {
+            Err("boom".into())
+        }
+    }
+
+    #[test]
+    fn empty_modulators_keeps_whole_path() {
+        let path = vec![Object::from(1_i64), Object::from(2_i64)];
+        assert_eq!(project_path(path.clone(), &[]).unwrap(), path);
+    }
+
+    #[test]
+    fn modulators_apply_round_robin_and_drop_none() {
+        let modulators: Vec<PathModulator> = vec![Box::new(AlwaysSome(9)), Box::new(AlwaysNone)];
+        let path = vec![Object::from(1_i64), Object::from(2_i64), Object::from(3_i64)];
+        let projected = project_path(path, &modulators).unwrap();
+        assert_eq!(projected, vec![Object::from(9_i64), Object::from(9_i64)]);
+    }
+
+    #[test]
+    fn modulator_error_propagates_instead_of_being_dropped() {
+        let modulators: Vec<PathModulator> = vec![Box::new(AlwaysErr)];
+        assert!(project_path(vec![Object::from(1_i64)], &modulators).is_err());
     }
 }
 
@@ -33,6 +110,7 @@ pub struct PathLocalCountStep {
 
 impl MapFunction<Traverser, Traverser> for PathLocalCountStep {
     fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        // `bulk` is left untouched; `traverser::global_count` weights by it downstream.
         let count = input.get_path_len() as i64;
         input.split_with_value(count, &self.tags);
         input.remove_tags(&self.remove_tags);
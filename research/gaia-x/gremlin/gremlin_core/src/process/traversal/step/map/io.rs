@@ -0,0 +1,283 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::traverser::{GraphElement, Traverser};
+use dyn_type::Object;
+use pegasus::api::function::*;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::sync::Mutex;
+
+/// Wire format used by [`ReadStep`]/[`WriteStep`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IoFormat {
+    GraphSON,
+    Binary,
+}
+
+/// `with()`-style options for an `io()` step.
+pub struct IoOptions {
+    pub path: String,
+    pub format: IoFormat,
+    pub batch_size: usize,
+}
+
+impl Default for IoOptions {
+    fn default() -> Self {
+        IoOptions { path: String::new(), format: IoFormat::GraphSON, batch_size: 1000 }
+    }
+}
+
+/// `read()`: parses GraphSON records from the configured source and emits one element
+/// traverser per record.
+pub struct ReadStep {
+    pub options: IoOptions,
+}
+
+impl FlatMapFunction<Traverser, Traverser> for ReadStep {
+    type Target = GraphsonLines;
+
+    fn exec(&self, _input: Traverser) -> FnResult<Self::Target> {
+        if self.options.format != IoFormat::GraphSON {
+            return Err("ReadStep currently only supports the GraphSON format".into());
+        }
+        let file = File::open(&self.options.path)?;
+        let reader = BufReader::with_capacity(self.options.batch_size.max(1) * 256, file);
+        Ok(GraphsonLines { lines: reader.lines() })
+    }
+}
+
+/// Iterator adapter that parses one GraphSON record per line as it is pulled. A line
+/// that is blank or fails to parse is skipped rather than failing the whole read.
+pub struct GraphsonLines {
+    lines: Lines<BufReader<File>>,
+}
+
+impl Iterator for GraphsonLines {
+    type Item = Traverser;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?.ok()?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(traverser) = graphson_to_traverser(&line) {
+                return Some(traverser);
+            }
+        }
+    }
+}
+
+/// `write()`: serializes every incoming traverser's head element to GraphSON and
+/// appends it to the configured sink.
+pub struct WriteStep {
+    pub options: IoOptions,
+    sink: Mutex<BufWriter<File>>,
+    pending: Mutex<usize>,
+}
+
+impl WriteStep {
+    pub fn new(options: IoOptions) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&options.path)?;
+        Ok(WriteStep { options, sink: Mutex::new(BufWriter::new(file)), pending: Mutex::new(0) })
+    }
+}
+
+impl MapFunction<Traverser, Traverser> for WriteStep {
+    fn exec(&self, input: Traverser) -> FnResult<Traverser> {
+        if self.options.format != IoFormat::GraphSON {
+            return Err("WriteStep currently only supports the GraphSON format".into());
+        }
+        let record = traverser_to_graphson(&input)?;
+        let mut sink = self.sink.lock().unwrap();
+        writeln!(sink, "{}", record)?;
+        let mut pending = self.pending.lock().unwrap();
+        *pending += 1;
+        if *pending >= self.options.batch_size {
+            sink.flush()?;
+            *pending = 0;
+        }
+        Ok(input)
+    }
+}
+
+/// Serializes an element's id/label (and, for edges, `outV`/`inV`) plus its properties
+/// into a single flat JSON object, one per line. This is a deliberately small subset of
+/// full GraphSON - only string-valued properties are supported - chosen so `read()`/
+/// `write()` don't need to pull in a full JSON/GraphSON codec dependency. Properties of
+/// any other `Object` variant are rejected rather than silently corrupted.
+fn traverser_to_graphson(traverser: &Traverser) -> FnResult<String> {
+    let element = traverser
+        .get_element()
+        .ok_or("WriteStep can only serialize traversers whose head is a graph element")?;
+    let mut fields = vec![
+        format!("\"id\":{}", element.id()),
+        format!("\"label\":{}", json_string(element.label())),
+    ];
+    if let Some((out_v, in_v)) = element.edge_endpoints() {
+        fields.push(format!("\"outV\":{}", out_v));
+        fields.push(format!("\"inV\":{}", in_v));
+    }
+    for (key, value) in element.get_all_properties() {
+        let Object::String(value) = value else {
+            return Err(format!(
+                "WriteStep only supports string-valued properties; \"{}\" is not a string",
+                key
+            )
+            .into());
+        };
+        fields.push(format!("\"p_{}\":{}", key, json_string(&value)));
+    }
+    Ok(format!("{{{}}}", fields.join(",")))
+}
+
+fn graphson_to_traverser(record: &str) -> FnResult<Traverser> {
+    let fields = parse_flat_json_object(record)?;
+    let id: u64 = fields
+        .get("id")
+        .ok_or("GraphSON record is missing \"id\"")?
+        .parse()
+        .map_err(|_| "GraphSON record has a non-numeric \"id\"")?;
+    let label =
+        fields.get("label").ok_or("GraphSON record is missing \"label\"")?.clone();
+    let mut element = match (fields.get("outV"), fields.get("inV")) {
+        (Some(out_v), Some(in_v)) => {
+            let out_v: u64 =
+                out_v.parse().map_err(|_| "GraphSON record has a non-numeric \"outV\"")?;
+            let in_v: u64 =
+                in_v.parse().map_err(|_| "GraphSON record has a non-numeric \"inV\"")?;
+            GraphElement::new_edge(id, label, out_v, in_v)
+        }
+        _ => GraphElement::new(id, label),
+    };
+    for (key, value) in &fields {
+        if let Some(prop_key) = key.strip_prefix("p_") {
+            element.set_property(prop_key.to_string(), Object::String(value.clone()));
+        }
+    }
+    Ok(Traverser::with_element(element))
+}
+
+/// Parses the flat (non-nested) `{"key":"value",...}` records written by
+/// [`traverser_to_graphson`] back into a key -> raw-value-text map.
+fn parse_flat_json_object(line: &str) -> FnResult<HashMap<String, String>> {
+    let inner = line
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or("GraphSON record is not a flat JSON object")?;
+    let mut fields = HashMap::new();
+    if inner.trim().is_empty() {
+        return Ok(fields);
+    }
+    for pair in split_top_level_commas(inner) {
+        let (key, value) =
+            pair.split_once(':').ok_or("GraphSON record has a field with no \":\"")?;
+        fields.insert(unquote(key.trim()), unquote(value.trim()));
+    }
+    Ok(fields)
+}
+
+/// Splits `s` on top-level commas, ignoring commas inside quoted strings.
+fn split_top_level_commas(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let bytes = s.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match b {
+            b'\\' if in_quotes => escaped = true,
+            b'"' => in_quotes = !in_quotes,
+            b',' if !in_quotes => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(s: &str) -> String {
+    match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_round_trips_through_graphson() {
+        let mut element = GraphElement::new(1, "person");
+        element.set_property("name", Object::from("marko"));
+        let traverser = Traverser::with_element(element);
+
+        let line = traverser_to_graphson(&traverser).unwrap();
+        let parsed = graphson_to_traverser(&line).unwrap();
+        let parsed = parsed.get_element().unwrap();
+
+        assert_eq!(parsed.id(), 1);
+        assert_eq!(parsed.label(), "person");
+        assert!(parsed.edge_endpoints().is_none());
+        assert_eq!(parsed.get_property("name"), Some(Object::from("marko")));
+    }
+
+    #[test]
+    fn non_string_properties_are_rejected_instead_of_silently_corrupted() {
+        let mut element = GraphElement::new(1, "person");
+        element.set_property("age", Object::from(29_i64));
+        let traverser = Traverser::with_element(element);
+
+        assert!(traverser_to_graphson(&traverser).is_err());
+    }
+
+    #[test]
+    fn edge_round_trips_in_and_out_vertex_refs() {
+        let element = GraphElement::new_edge(99, "knows", 1, 7);
+        let traverser = Traverser::with_element(element);
+
+        let line = traverser_to_graphson(&traverser).unwrap();
+        let parsed = graphson_to_traverser(&line).unwrap();
+        let parsed = parsed.get_element().unwrap();
+
+        assert_eq!(parsed.edge_endpoints(), Some((1, 7)));
+    }
+
+    #[test]
+    fn write_step_rejects_binary_format_like_read_step_does() {
+        let dir = std::env::temp_dir().join(format!("io_rs_write_step_test_{}", std::process::id()));
+        let options = IoOptions { path: dir.to_string_lossy().into_owned(), format: IoFormat::Binary, batch_size: 10 };
+        let write_step = WriteStep::new(options).unwrap();
+        let element = GraphElement::new(1, "person");
+        let result = write_step.exec(Traverser::with_element(element));
+        assert!(result.is_err());
+        let _ = std::fs::remove_file(dir);
+    }
+}
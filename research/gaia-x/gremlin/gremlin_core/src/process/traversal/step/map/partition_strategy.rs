@@ -0,0 +1,192 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::process::traversal::traverser::Traverser;
+use bit_set::BitSet;
+use dyn_type::Object;
+use pegasus::api::function::*;
+
+/// Restricts a traversal to a named partition, analogous to TinkerPop's
+/// `PartitionStrategy`.
+#[derive(Clone)]
+pub struct PartitionConfig {
+    pub partition_key: String,
+    pub write_partition: String,
+    pub read_partitions: Vec<String>,
+}
+
+/// Drops any element whose `partition_key` property is not one of the configured
+/// `read_partitions`, equivalent to an implicit `has(partitionKey, within(readPartitions))`.
+pub struct PartitionFilterStep {
+    pub config: PartitionConfig,
+}
+
+impl FilterFunction<Traverser> for PartitionFilterStep {
+    fn test(&self, input: &Traverser) -> FnResult<bool> {
+        let element = input
+            .get_element()
+            .ok_or("PartitionFilterStep expects the traverser head to be a graph element")?;
+        let in_scope = match element.get_property(&self.config.partition_key) {
+            Some(Object::String(partition)) => {
+                self.config.read_partitions.iter().any(|p| p == &partition)
+            }
+            _ => false,
+        };
+        Ok(in_scope)
+    }
+}
+
+/// Tags every newly produced element with the configured `write_partition`.
+pub struct PartitionWriteStep {
+    pub config: PartitionConfig,
+    pub tags: BitSet,
+    pub remove_tags: BitSet,
+}
+
+impl MapFunction<Traverser, Traverser> for PartitionWriteStep {
+    fn exec(&self, mut input: Traverser) -> FnResult<Traverser> {
+        let element = input
+            .get_element_mut()
+            .ok_or("PartitionWriteStep expects the traverser head to be a graph element")?;
+        element.set_property(
+            self.config.partition_key.clone(),
+            Object::String(self.config.write_partition.clone()),
+        );
+        input.remove_tags(&self.remove_tags);
+        Ok(input)
+    }
+}
+
+/// A step in the plan being rewritten by [`PartitionStrategy`], tagged by whether it
+/// reads existing elements, creates new ones, or is unrelated.
+pub enum PlanStep {
+    /// E.g. `V()`, `E()`, `out()`, `in()`, `both()`.
+    ReadsElements(Box<dyn MapFunction<Traverser, Traverser>>),
+    /// E.g. `addV()`, `addE()`.
+    CreatesElements(Box<dyn MapFunction<Traverser, Traverser>>),
+    /// Any other step, left untouched by the strategy.
+    Other(Box<dyn MapFunction<Traverser, Traverser>>),
+}
+
+/// A decoration strategy, analogous to TinkerPop's `PartitionStrategy`, that rewrites a
+/// step plan to scope it to one named partition of a shared physical store.
+pub struct PartitionStrategy {
+    pub config: PartitionConfig,
+}
+
+impl PartitionStrategy {
+    pub fn new(config: PartitionConfig) -> Self {
+        PartitionStrategy { config }
+    }
+
+    /// Consumes the untagged step plan and returns the rewritten plan with filter/write
+    /// steps spliced in.
+    pub fn apply(&self, plan: Vec<PlanStep>) -> Vec<PartitionPlanItem> {
+        let mut rewritten = Vec::with_capacity(plan.len() + plan.len() / 2);
+        for step in plan {
+            match step {
+                PlanStep::ReadsElements(function) => {
+                    rewritten.push(PartitionPlanItem::Map(function));
+                    rewritten.push(PartitionPlanItem::Filter(Box::new(PartitionFilterStep {
+                        config: self.config.clone(),
+                    })));
+                }
+                PlanStep::CreatesElements(function) => {
+                    rewritten.push(PartitionPlanItem::Map(function));
+                    rewritten.push(PartitionPlanItem::Map(Box::new(PartitionWriteStep {
+                        config: self.config.clone(),
+                        tags: BitSet::new(),
+                        remove_tags: BitSet::new(),
+                    })));
+                }
+                PlanStep::Other(function) => rewritten.push(PartitionPlanItem::Map(function)),
+            }
+        }
+        rewritten
+    }
+}
+
+/// One entry of a rewritten plan: either a map step (the original step, or an injected
+/// [`PartitionWriteStep`]) or a filter step (an injected [`PartitionFilterStep`]).
+pub enum PartitionPlanItem {
+    Map(Box<dyn MapFunction<Traverser, Traverser>>),
+    Filter(Box<dyn FilterFunction<Traverser>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::traversal::traverser::GraphElement;
+
+    struct Noop;
+    impl MapFunction<Traverser, Traverser> for Noop {
+        fn exec(&self, input: Traverser) -> FnResult<Traverser> {
+            Ok(input)
+        }
+    }
+
+    fn config() -> PartitionConfig {
+        PartitionConfig {
+            partition_key: "partition".to_string(),
+            write_partition: "p1".to_string(),
+            read_partitions: vec!["p1".to_string()],
+        }
+    }
+
+    #[test]
+    fn filter_step_keeps_elements_in_a_read_partition_and_drops_others() {
+        let step = PartitionFilterStep { config: config() };
+
+        let mut in_partition = GraphElement::new(1, "person");
+        in_partition.set_property("partition", Object::from("p1"));
+        assert!(step.test(&Traverser::with_element(in_partition)).unwrap());
+
+        let mut out_of_partition = GraphElement::new(2, "person");
+        out_of_partition.set_property("partition", Object::from("p2"));
+        assert!(!step.test(&Traverser::with_element(out_of_partition)).unwrap());
+
+        let missing_partition = GraphElement::new(3, "person");
+        assert!(!step.test(&Traverser::with_element(missing_partition)).unwrap());
+    }
+
+    #[test]
+    fn write_step_tags_new_elements_with_write_partition() {
+        let step =
+            PartitionWriteStep { config: config(), tags: BitSet::new(), remove_tags: BitSet::new() };
+        let element = GraphElement::new(1, "person");
+        let output = step.exec(Traverser::with_element(element)).unwrap();
+        let tagged = output.get_element().unwrap();
+        assert_eq!(tagged.get_property("partition"), Some(Object::String("p1".to_string())));
+    }
+
+    #[test]
+    fn rewrite_splices_filter_after_reads_and_write_after_creates() {
+        let plan = vec![
+            PlanStep::ReadsElements(Box::new(Noop)),
+            PlanStep::Other(Box::new(Noop)),
+            PlanStep::CreatesElements(Box::new(Noop)),
+        ];
+        let strategy = PartitionStrategy::new(config());
+        let rewritten = strategy.apply(plan);
+
+        // ReadsElements -> [map, filter], Other -> [map], CreatesElements -> [map, map]
+        assert_eq!(rewritten.len(), 5);
+        assert!(matches!(rewritten[0], PartitionPlanItem::Map(_)));
+        assert!(matches!(rewritten[1], PartitionPlanItem::Filter(_)));
+        assert!(matches!(rewritten[2], PartitionPlanItem::Map(_)));
+        assert!(matches!(rewritten[3], PartitionPlanItem::Map(_)));
+        assert!(matches!(rewritten[4], PartitionPlanItem::Map(_)));
+    }
+}
@@ -0,0 +1,245 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use bit_set::BitSet;
+use dyn_type::Object;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A vertex or edge carried by a [`Traverser`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphElement {
+    id: u64,
+    label: String,
+    properties: HashMap<String, Object>,
+    /// `(out_v, in_v)` when this element is an edge; `None` for a vertex.
+    edge_endpoints: Option<(u64, u64)>,
+}
+
+impl GraphElement {
+    pub fn new(id: u64, label: impl Into<String>) -> Self {
+        GraphElement { id, label: label.into(), properties: HashMap::new(), edge_endpoints: None }
+    }
+
+    pub fn new_edge(id: u64, label: impl Into<String>, out_v: u64, in_v: u64) -> Self {
+        GraphElement {
+            id,
+            label: label.into(),
+            properties: HashMap::new(),
+            edge_endpoints: Some((out_v, in_v)),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn edge_endpoints(&self) -> Option<(u64, u64)> {
+        self.edge_endpoints
+    }
+
+    pub fn get_property(&self, key: &str) -> Option<Object> {
+        self.properties.get(key).cloned()
+    }
+
+    pub fn get_all_properties(&self) -> Vec<(String, Object)> {
+        self.properties.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn set_property(&mut self, key: impl Into<String>, value: Object) {
+        self.properties.insert(key.into(), value);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TraverserValue {
+    Object(Object),
+    Element(GraphElement),
+}
+
+/// The unit of data flowing through a Pegasus dataflow for a Gremlin traversal. `bulk`
+/// denotes how many logically identical values this one traverser stands in for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Traverser {
+    value: TraverserValue,
+    bulk: i64,
+    path: Vec<Object>,
+    tags: HashMap<usize, Object>,
+}
+
+impl Traverser {
+    pub fn new(value: Object) -> Self {
+        Traverser { value: TraverserValue::Object(value), bulk: 1, path: Vec::new(), tags: HashMap::new() }
+    }
+
+    pub fn with_element(element: GraphElement) -> Self {
+        Traverser { value: TraverserValue::Element(element), bulk: 1, path: Vec::new(), tags: HashMap::new() }
+    }
+
+    pub fn get_bulk(&self) -> i64 {
+        self.bulk
+    }
+
+    pub fn set_bulk(&mut self, bulk: i64) {
+        self.bulk = bulk;
+    }
+
+    pub fn take_path(&mut self) -> Vec<Object> {
+        std::mem::take(&mut self.path)
+    }
+
+    pub fn get_path_len(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn get_object(&self) -> Option<&Object> {
+        match &self.value {
+            TraverserValue::Object(value) => Some(value),
+            TraverserValue::Element(_) => None,
+        }
+    }
+
+    pub fn get_element(&self) -> Option<&GraphElement> {
+        match &self.value {
+            TraverserValue::Element(element) => Some(element),
+            TraverserValue::Object(_) => None,
+        }
+    }
+
+    pub fn get_element_mut(&mut self) -> Option<&mut GraphElement> {
+        match &mut self.value {
+            TraverserValue::Element(element) => Some(element),
+            TraverserValue::Object(_) => None,
+        }
+    }
+
+    pub fn split_with_value(&mut self, value: impl Into<Object>, tags: &BitSet) {
+        let value = value.into();
+        for tag in tags.iter() {
+            self.tags.entry(tag).or_insert_with(|| value.clone());
+        }
+        self.value = TraverserValue::Object(value);
+    }
+
+    pub fn remove_tags(&mut self, tags: &BitSet) {
+        for tag in tags.iter() {
+            self.tags.remove(&tag);
+        }
+    }
+
+    /// Whether `self` and `other` are interchangeable other than `bulk`.
+    pub fn shallow_eq(&self, other: &Traverser) -> bool {
+        self.value == other.value && self.path == other.path && self.tags == other.tags
+    }
+}
+
+/// A coarse, collision-tolerant hash of a traverser's value/path/tag state, used only to
+/// bucket candidates in [`merge_bulk`]. Correctness comes from `Traverser::shallow_eq`
+/// (a real `PartialEq` comparison, so e.g. `NaN != NaN`), not from this hash being exact.
+fn coarse_hash(traverser: &Traverser) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", traverser.value).hash(&mut hasher);
+    traverser.path.len().hash(&mut hasher);
+    traverser.tags.len().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Collapses traversers that are [`Traverser::shallow_eq`] by summing their bulks.
+/// Candidates are bucketed by [`coarse_hash`] so each incoming traverser is compared
+/// against only its own bucket rather than the full accumulated list, avoiding an O(n^2)
+/// scan at high fan-out while still using real equality (not a stringified key) to
+/// decide what merges.
+pub fn merge_bulk(traversers: Vec<Traverser>) -> Vec<Traverser> {
+    let mut merged: Vec<Traverser> = Vec::with_capacity(traversers.len());
+    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::with_capacity(traversers.len());
+    for traverser in traversers {
+        let hash = coarse_hash(&traverser);
+        let bucket = buckets.entry(hash).or_default();
+        match bucket.iter().find(|&&i| merged[i].shallow_eq(&traverser)) {
+            Some(&i) => {
+                let bulk = merged[i].get_bulk() + traverser.get_bulk();
+                merged[i].set_bulk(bulk);
+            }
+            None => {
+                bucket.push(merged.len());
+                merged.push(traverser);
+            }
+        }
+    }
+    merged
+}
+
+/// Sums `bulk` across all traversers, rather than counting each traverser as one.
+pub fn global_count(traversers: &[Traverser]) -> i64 {
+    traversers.iter().map(Traverser::get_bulk).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_count_sums_bulk_not_traverser_count() {
+        let mut a = Traverser::new(Object::from(1_i64));
+        a.set_bulk(4);
+        let mut b = Traverser::new(Object::from(2_i64));
+        b.set_bulk(6);
+        assert_eq!(global_count(&[a, b]), 10);
+    }
+
+    #[test]
+    fn split_with_value_preserves_bulk() {
+        let mut traverser = Traverser::new(Object::from(1_i64));
+        traverser.set_bulk(3);
+        let tags = BitSet::new();
+        traverser.split_with_value(Object::from(2_i64), &tags);
+        assert_eq!(traverser.get_bulk(), 3);
+    }
+
+    #[test]
+    fn merge_bulk_sums_equal_traversers_and_keeps_distinct_ones() {
+        let a = Traverser::new(Object::from(1_i64));
+        let b = Traverser::new(Object::from(1_i64));
+        let mut c = Traverser::new(Object::from(2_i64));
+        c.set_bulk(5);
+
+        let merged = merge_bulk(vec![a, b, c]);
+        assert_eq!(merged.len(), 2);
+        let total_bulk: i64 = merged.iter().map(Traverser::get_bulk).sum();
+        assert_eq!(total_bulk, 1 + 1 + 5);
+    }
+
+    #[test]
+    fn shallow_eq_ignores_bulk() {
+        let mut a = Traverser::new(Object::from(1_i64));
+        let mut b = Traverser::new(Object::from(1_i64));
+        a.set_bulk(10);
+        b.set_bulk(1);
+        assert!(a.shallow_eq(&b));
+    }
+
+    #[test]
+    fn nan_valued_traversers_are_not_merged() {
+        let a = Traverser::new(Object::from(f64::NAN));
+        let b = Traverser::new(Object::from(f64::NAN));
+        let merged = merge_bulk(vec![a, b]);
+        assert_eq!(merged.len(), 2, "NaN != NaN, so these must not be merged despite colliding hash buckets");
+    }
+}